@@ -1,9 +1,14 @@
 use data_encoding::BASE32;
 use rocket::serde::json::Json;
 use rocket::Route;
+use totp_lite::{totp_custom, Sha1, Sha256, Sha512};
+use url::Url;
 
 use crate::{
-    api::{core::log_user_event, core::two_factor::_generate_recover_code, EmptyResult, JsonResult, PasswordOrOtpData},
+    api::{
+        core::log_user_event, core::two_factor::_generate_recover_code, ApiResult, EmptyResult, JsonResult,
+        PasswordOrOtpData,
+    },
     auth::{ClientIp, Headers},
     crypto,
     db::{
@@ -19,6 +24,229 @@ pub fn routes() -> Vec<Route> {
     routes![generate_authenticator, activate_authenticator, activate_authenticator_put, disable_authenticator]
 }
 
+/// The hash algorithm a TOTP code is generated with, as specified by an `otpauth://` URI's
+/// `algorithm` parameter. Defaults to SHA1 to match the previously hardcoded behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+enum TotpAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl Default for TotpAlgorithm {
+    fn default() -> Self {
+        Self::Sha1
+    }
+}
+
+fn default_digits() -> u32 {
+    6
+}
+
+fn default_period() -> u64 {
+    30
+}
+
+/// Upper bound on `period`, generous enough for any real otpauth URI while keeping
+/// `step * period` (authenticator.rs validation loop) far from overflowing an `i64`.
+const MAX_PERIOD_SECONDS: u64 = 300;
+
+/// Everything needed to validate a TOTP code, persisted as JSON in `TwoFactor.data`. Parsed
+/// either from a full `otpauth://totp/...` URI, which can override any of the defaults below,
+/// or from a raw BASE32 secret, which keeps the historical SHA1/6-digit/30-second behavior.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TotpSecret {
+    secret: String,
+    #[serde(default)]
+    algorithm: TotpAlgorithm,
+    #[serde(default = "default_digits")]
+    digits: u32,
+    #[serde(default = "default_period")]
+    period: u64,
+    /// Exponential moving average of the `step` offset of recent successful validations, used
+    /// to slide the drift window to follow a client with a stable clock skew instead of always
+    /// centering it on zero.
+    #[serde(default)]
+    drift_offset: f64,
+    /// Consecutive invalid codes since the last successful validation or lockout.
+    #[serde(default)]
+    failed_attempts: u32,
+    /// How many times this secret has been locked out before, used to grow the cooldown.
+    #[serde(default)]
+    lockout_count: u32,
+    /// Unix timestamp until which all codes are rejected, regardless of validity.
+    #[serde(default)]
+    locked_until: Option<i64>,
+}
+
+/// Weight given to the latest observed drift `step` when updating `drift_offset`'s EMA.
+const DRIFT_EMA_ALPHA: f64 = 0.3;
+
+/// Doubles `base_seconds` for every prior lockout (capped so the exponent can't overflow),
+/// giving repeated lockouts a progressively longer cooldown.
+fn lockout_cooldown_seconds(base_seconds: i64, lockout_count: u32) -> i64 {
+    base_seconds.max(1) * 2i64.pow(lockout_count.min(10))
+}
+
+/// Records an invalid TOTP code, locking out further attempts with exponential backoff once
+/// `authenticator_max_failed_attempts` consecutive failures are reached. Only persists against
+/// `record_exists`: a code rejected while still setting up 2FA (the record hasn't been saved
+/// yet, i.e. isn't enabled) must not leave a partially-enabled TwoFactor row behind -- that would
+/// survive an abandoned or mistyped setup with no recovery codes generated for it. Brute-force
+/// protection during that window is already covered by the password/OTP re-check the activation
+/// endpoint requires before ever reaching here.
+async fn record_totp_failure(
+    totp_secret: &mut TotpSecret,
+    twofactor: &mut TwoFactor,
+    record_exists: bool,
+    conn: &mut DbConn,
+) -> EmptyResult {
+    if !record_exists {
+        return Ok(());
+    }
+
+    totp_secret.failed_attempts += 1;
+
+    if totp_secret.failed_attempts >= CONFIG.authenticator_max_failed_attempts().max(1) as u32 {
+        let cooldown = lockout_cooldown_seconds(CONFIG.authenticator_lockout_seconds(), totp_secret.lockout_count);
+        totp_secret.locked_until = Some(chrono::Utc::now().timestamp() + cooldown);
+        totp_secret.lockout_count += 1;
+        totp_secret.failed_attempts = 0;
+        warn!("TOTP lockout triggered for user after repeated invalid codes, locked for {cooldown} seconds");
+    }
+
+    twofactor.data = serde_json::to_string(&*totp_secret)?;
+    twofactor.save(conn).await
+}
+
+impl TotpSecret {
+    /// Parses user-supplied `key`: a full `otpauth://` URI, or else a raw BASE32 secret with the
+    /// historical SHA1/6-digit/30-second defaults. Deliberately never accepts the stored JSON
+    /// form here -- that's only ever produced by our own serialization of already-validated
+    /// data, and accepting it from untrusted input would let a caller inject arbitrary internal
+    /// state (lockout counters, drift offset, out-of-range digits/period) into their own record.
+    /// Also returns whether `key` carried its own parameters (a URI), since that relaxes the
+    /// 20-byte length check that otherwise only applies to freshly entered raw secrets.
+    fn parse(key: &str) -> ApiResult<(Self, bool)> {
+        if key.starts_with("otpauth://") {
+            return Ok((Self::parse_uri(key)?, true));
+        }
+
+        Ok((
+            Self {
+                secret: key.to_uppercase(),
+                algorithm: TotpAlgorithm::default(),
+                digits: default_digits(),
+                period: default_period(),
+                drift_offset: 0.0,
+                failed_attempts: 0,
+                lockout_count: 0,
+                locked_until: None,
+            },
+            false,
+        ))
+    }
+
+    /// Parses the JSON form `TwoFactor.data` is persisted in, falling back to a raw BASE32
+    /// secret for records written before otpauth URI support existed. Only ever called with our
+    /// own previously-validated data (`TwoFactor.data`), never with user-supplied input.
+    fn parse_stored(data: &str) -> ApiResult<Self> {
+        let parsed = match serde_json::from_str::<Self>(data) {
+            Ok(parsed) => parsed,
+            _ => Self {
+                secret: data.to_uppercase(),
+                algorithm: TotpAlgorithm::default(),
+                digits: default_digits(),
+                period: default_period(),
+                drift_offset: 0.0,
+                failed_attempts: 0,
+                lockout_count: 0,
+                locked_until: None,
+            },
+        };
+
+        parsed.validate_params()?;
+        Ok(parsed)
+    }
+
+    /// Enforces the bounds every `TotpSecret`, however it was parsed, must satisfy before it's
+    /// used to generate or validate a code.
+    fn validate_params(&self) -> EmptyResult {
+        if !(6..=8).contains(&self.digits) {
+            err!("Invalid TOTP digits: must be 6, 7 or 8")
+        }
+
+        if self.period == 0 || self.period > MAX_PERIOD_SECONDS {
+            err!("Invalid TOTP period")
+        }
+
+        Ok(())
+    }
+
+    fn parse_uri(uri: &str) -> ApiResult<Self> {
+        let Ok(url) = Url::parse(uri) else {
+            err!("Invalid otpauth URI")
+        };
+
+        // `otpauth` isn't a special URL scheme, so `Url` doesn't lowercase its host the way it
+        // would for e.g. `http`; compare case-insensitively since some authenticators emit
+        // `otpauth://TOTP/...`.
+        if url.scheme() != "otpauth" || !url.host_str().is_some_and(|host| host.eq_ignore_ascii_case("totp")) {
+            err!("Invalid otpauth URI: only otpauth://totp is supported")
+        }
+
+        let mut secret = None;
+        let mut algorithm = TotpAlgorithm::default();
+        let mut digits = default_digits();
+        let mut period = default_period();
+
+        for (param, value) in url.query_pairs() {
+            match &*param {
+                "secret" => secret = Some(value.to_uppercase()),
+                "algorithm" => {
+                    algorithm = match value.to_uppercase().as_str() {
+                        "SHA1" => TotpAlgorithm::Sha1,
+                        "SHA256" => TotpAlgorithm::Sha256,
+                        "SHA512" => TotpAlgorithm::Sha512,
+                        _ => err!("Invalid otpauth URI: unsupported algorithm"),
+                    }
+                }
+                "digits" => {
+                    digits = match value.parse() {
+                        Ok(d) if (6..=8).contains(&d) => d,
+                        _ => err!("Invalid otpauth URI: digits must be 6, 7 or 8"),
+                    }
+                }
+                "period" => {
+                    period = match value.parse() {
+                        Ok(p) if p > 0 && p <= MAX_PERIOD_SECONDS => p,
+                        _ => err!("Invalid otpauth URI: invalid period"),
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let Some(secret) = secret else {
+            err!("Invalid otpauth URI: missing secret")
+        };
+
+        let parsed = Self {
+            secret,
+            algorithm,
+            digits,
+            period,
+            drift_offset: 0.0,
+            failed_attempts: 0,
+            lockout_count: 0,
+            locked_until: None,
+        };
+        parsed.validate_params()?;
+        Ok(parsed)
+    }
+}
+
 #[post("/two-factor/get-authenticator", data = "<data>")]
 async fn generate_authenticator(data: Json<PasswordOrOtpData>, headers: Headers, mut conn: DbConn) -> JsonResult {
     let data: PasswordOrOtpData = data.into_inner();
@@ -30,7 +258,10 @@ async fn generate_authenticator(data: Json<PasswordOrOtpData>, headers: Headers,
     let twofactor = TwoFactor::find_by_user_and_type(&user.uuid, type_, &mut conn).await;
 
     let (enabled, key) = match twofactor {
-        Some(tf) => (true, tf.data),
+        Some(tf) => {
+            let totp_secret = TotpSecret::parse_stored(&tf.data)?;
+            (true, totp_secret.secret)
+        }
         _ => (false, crypto::encode_random_bytes::<20>(BASE32)),
     };
 
@@ -69,18 +300,23 @@ async fn activate_authenticator(data: Json<EnableAuthenticatorData>, headers: He
     .validate(&user, true, &mut conn)
     .await?;
 
-    // Validate key as base32 and 20 bytes length
-    let decoded_key: Vec<u8> = match BASE32.decode(key.as_bytes()) {
+    // Accept either a raw BASE32 secret or a full otpauth:// URI.
+    let (totp_secret, from_uri) = TotpSecret::parse(&key)?;
+
+    // Validate the decoded secret as BASE32. The legacy 20-byte length requirement only applies
+    // when it wasn't supplied via a URI, since non-SHA1 secrets are commonly longer.
+    let decoded_key: Vec<u8> = match BASE32.decode(totp_secret.secret.as_bytes()) {
         Ok(decoded) => decoded,
         _ => err!("Invalid totp secret"),
     };
 
-    if decoded_key.len() != 20 {
+    if !from_uri && decoded_key.len() != 20 {
         err!("Invalid key length")
     }
 
     // Validate the token provided with the key, and save new twofactor
-    validate_totp_code(&user.uuid, &token, &key.to_uppercase(), &headers.ip, &mut conn).await?;
+    let stored_key = serde_json::to_string(&totp_secret)?;
+    validate_totp_code(&user.uuid, &token, &stored_key, &headers.ip, &mut conn).await?;
 
     _generate_recover_code(&mut user, &mut conn).await;
 
@@ -88,7 +324,7 @@ async fn activate_authenticator(data: Json<EnableAuthenticatorData>, headers: He
 
     Ok(Json(json!({
         "enabled": true,
-        "key": key,
+        "key": totp_secret.secret,
         "object": "twoFactorAuthenticator"
     })))
 }
@@ -105,7 +341,8 @@ pub async fn validate_totp_code_str(
     ip: &ClientIp,
     conn: &mut DbConn,
 ) -> EmptyResult {
-    if !totp_code.chars().all(char::is_numeric) {
+    // Numeric-only, with no fixed length check, so 6/7/8-digit codes are all accepted here.
+    if totp_code.is_empty() || !totp_code.chars().all(char::is_numeric) {
         err!("TOTP code is not a number");
     }
 
@@ -119,34 +356,94 @@ pub async fn validate_totp_code(
     ip: &ClientIp,
     conn: &mut DbConn,
 ) -> EmptyResult {
-    use totp_lite::{totp_custom, Sha1};
+    // `secret` is always our own stored/serialized form here, never raw user input: the login
+    // path passes `twofactor.data` directly, and `activate_authenticator` already parsed the
+    // user-supplied key with the untrusted `TotpSecret::parse` and re-serialized the validated
+    // result before calling us. So it's safe -- and necessary, for legacy raw-secret records --
+    // to read it with `parse_stored`.
+    let input_secret = TotpSecret::parse_stored(secret)?;
+
+    let (mut twofactor, record_exists) =
+        match TwoFactor::find_by_user_and_type(user_id, TwoFactorType::Authenticator as i32, conn).await {
+            Some(tf) => (tf, true),
+            _ => (
+                TwoFactor::new(user_id.clone(), TwoFactorType::Authenticator, serde_json::to_string(&input_secret)?),
+                false,
+            ),
+        };
+
+    // The crypto parameters (secret/algorithm/digits/period) come from whatever is being
+    // validated right now -- `secret` itself during activation, or the persisted record during
+    // login, where the two are identical. The mutable validation state (drift offset, failure
+    // counters, lockout) must always be sourced from the persisted record instead: activation
+    // re-serializes a fresh, zeroed `TotpSecret` on every call, so reading it from `secret` would
+    // let an attacker reset the lockout just by submitting a different candidate secret each time.
+    let mut totp_secret = input_secret;
+    if let Ok(persisted) = TotpSecret::parse_stored(&twofactor.data) {
+        totp_secret.drift_offset = persisted.drift_offset;
+        totp_secret.failed_attempts = persisted.failed_attempts;
+        totp_secret.lockout_count = persisted.lockout_count;
+        totp_secret.locked_until = persisted.locked_until;
+    }
 
-    let Ok(decoded_secret) = BASE32.decode(secret.as_bytes()) else {
+    let Ok(decoded_secret) = BASE32.decode(totp_secret.secret.as_bytes()) else {
         err!("Invalid TOTP secret")
     };
 
-    let mut twofactor = match TwoFactor::find_by_user_and_type(user_id, TwoFactorType::Authenticator as i32, conn).await
-    {
-        Some(tf) => tf,
-        _ => TwoFactor::new(user_id.clone(), TwoFactorType::Authenticator, secret.to_string()),
-    };
+    // Reject outright while locked out, regardless of whether the code would otherwise be valid.
+    // NOTE: this reuses `EventType::UserFailedLogIn2fa` rather than a dedicated lockout event,
+    // since that's the only TOTP-failure event type defined today; a lockout is distinguishable
+    // in the event log only by its message text, not by event type. Add a dedicated
+    // `UserFailedLogIn2faLockout`-style variant the next time `EventType` itself is touched.
+    if let Some(locked_until) = totp_secret.locked_until {
+        if locked_until > chrono::Utc::now().timestamp() {
+            err!(
+                "Too many invalid TOTP codes have been entered. Please wait before trying again.",
+                ErrorEvent {
+                    event: EventType::UserFailedLogIn2fa
+                }
+            );
+        }
+    }
 
-    // The amount of steps back and forward in time
-    // Also check if we need to disable time drifted TOTP codes.
-    // If that is the case, we set the steps to 0 so only the current TOTP is valid.
-    let steps = i64::from(!CONFIG.authenticator_disable_time_drift());
+    // The amount of steps back and forward in time we allow.
+    // `authenticator_disable_time_drift` forces this to 0 so only the current TOTP is valid,
+    // otherwise `authenticator_time_drift_steps` is used directly, clamped to a sane upper
+    // bound so the validation window can't be opened absurdly wide.
+    let steps = if CONFIG.authenticator_disable_time_drift() {
+        0
+    } else {
+        CONFIG.authenticator_time_drift_steps().clamp(0, 10)
+    };
 
     // Get the current system time in UNIX Epoch (UTC)
     let current_time = chrono::Utc::now();
     let current_timestamp = current_time.timestamp();
+    let period = totp_secret.period as i64;
+
+    // Center the search window on the stored drift offset instead of zero, so a client with a
+    // stable clock skew stays inside the window instead of repeatedly landing on its edge.
+    // Clamped to `steps` (and pinned to 0 when drift is disabled) so the current step (0) is
+    // always inside the window: without this, an offset that ratcheted out past `steps` while
+    // following a growing skew would permanently exclude the client's true code once its clock
+    // is corrected, since `drift_offset` only moves on success and could never recenter.
+    let offset = if steps == 0 {
+        0
+    } else {
+        totp_secret.drift_offset.round().clamp(-steps as f64, steps as f64) as i64
+    };
 
-    for step in -steps..=steps {
-        let time_step = current_timestamp / 30i64 + step;
+    for step in (-steps + offset)..=(steps + offset) {
+        let time_step = current_timestamp / period + step;
 
         // We need to calculate the time offsite and cast it as an u64.
         // Since we only have times into the future and the totp generator needs an u64 instead of the default i64.
-        let time = (current_timestamp + step * 30i64) as u64;
-        let generated = totp_custom::<Sha1>(30, 6, &decoded_secret, time);
+        let time = (current_timestamp + step * period) as u64;
+        let generated = match totp_secret.algorithm {
+            TotpAlgorithm::Sha1 => totp_custom::<Sha1>(totp_secret.period, totp_secret.digits, &decoded_secret, time),
+            TotpAlgorithm::Sha256 => totp_custom::<Sha256>(totp_secret.period, totp_secret.digits, &decoded_secret, time),
+            TotpAlgorithm::Sha512 => totp_custom::<Sha512>(totp_secret.period, totp_secret.digits, &decoded_secret, time),
+        };
 
         // Check the given code equals the generated and if the time_step is larger then the one last used.
         if generated == totp_code && time_step > twofactor.last_used {
@@ -155,13 +452,24 @@ pub async fn validate_totp_code(
                 warn!("TOTP Time drift detected. The step offset is {step}");
             }
 
+            // Slide the drift offset towards this step so a consistently skewed client's window
+            // keeps following it.
+            totp_secret.drift_offset = totp_secret.drift_offset * (1.0 - DRIFT_EMA_ALPHA) + (step as f64) * DRIFT_EMA_ALPHA;
+
+            // A successful validation clears any accumulated failures and lockouts.
+            totp_secret.failed_attempts = 0;
+            totp_secret.lockout_count = 0;
+            totp_secret.locked_until = None;
+
             // Save the last used time step so only totp time steps higher then this one are allowed.
             // This will also save a newly created twofactor if the code is correct.
             twofactor.last_used = time_step;
+            twofactor.data = serde_json::to_string(&totp_secret)?;
             twofactor.save(conn).await?;
             return Ok(());
         } else if generated == totp_code && time_step <= twofactor.last_used {
             warn!("This TOTP or a TOTP code within {steps} steps back or forward has already been used!");
+            record_totp_failure(&mut totp_secret, &mut twofactor, record_exists, conn).await?;
             err!(
                 format!("Invalid TOTP code! Server time: {} IP: {}", current_time.format("%F %T UTC"), ip.ip),
                 ErrorEvent {
@@ -172,6 +480,7 @@ pub async fn validate_totp_code(
     }
 
     // Else no valid code received, deny access
+    record_totp_failure(&mut totp_secret, &mut twofactor, record_exists, conn).await?;
     err!(
         format!("Invalid TOTP code! Server time: {} IP: {}", current_time.format("%F %T UTC"), ip.ip),
         ErrorEvent {
@@ -198,7 +507,8 @@ async fn disable_authenticator(data: Json<DisableAuthenticatorData>, headers: He
     }
 
     if let Some(twofactor) = TwoFactor::find_by_user_and_type(&user.uuid, type_, &mut conn).await {
-        if twofactor.data == data.key {
+        let stored_secret = TotpSecret::parse_stored(&twofactor.data)?;
+        if stored_secret.secret == data.key {
             twofactor.delete(&mut conn).await?;
             log_user_event(
                 EventType::UserDisabled2fa as i32,
@@ -223,3 +533,97 @@ async fn disable_authenticator(data: Json<DisableAuthenticatorData>, headers: He
         "object": "twoFactorProvider"
     })))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_uri_reads_secret_and_overrides() {
+        let totp_secret =
+            TotpSecret::parse_uri("otpauth://totp/Example:alice@example.com?secret=JBSWY3DPEHPK3PXP&algorithm=SHA256&digits=8&period=60")
+                .unwrap();
+
+        assert_eq!(totp_secret.secret, "JBSWY3DPEHPK3PXP");
+        assert_eq!(totp_secret.algorithm, TotpAlgorithm::Sha256);
+        assert_eq!(totp_secret.digits, 8);
+        assert_eq!(totp_secret.period, 60);
+    }
+
+    #[test]
+    fn parse_uri_defaults_match_legacy_behavior() {
+        let totp_secret = TotpSecret::parse_uri("otpauth://totp/Example?secret=JBSWY3DPEHPK3PXP").unwrap();
+
+        assert_eq!(totp_secret.algorithm, TotpAlgorithm::Sha1);
+        assert_eq!(totp_secret.digits, 6);
+        assert_eq!(totp_secret.period, 30);
+    }
+
+    #[test]
+    fn parse_uri_host_is_case_insensitive() {
+        assert!(TotpSecret::parse_uri("otpauth://TOTP/Example?secret=JBSWY3DPEHPK3PXP").is_ok());
+    }
+
+    #[test]
+    fn parse_uri_rejects_missing_secret() {
+        assert!(TotpSecret::parse_uri("otpauth://totp/Example?algorithm=SHA1").is_err());
+    }
+
+    #[test]
+    fn parse_uri_rejects_unsupported_algorithm() {
+        assert!(TotpSecret::parse_uri("otpauth://totp/Example?secret=JBSWY3DPEHPK3PXP&algorithm=MD5").is_err());
+    }
+
+    #[test]
+    fn lockout_cooldown_grows_exponentially() {
+        assert_eq!(lockout_cooldown_seconds(30, 0), 30);
+        assert_eq!(lockout_cooldown_seconds(30, 1), 60);
+        assert_eq!(lockout_cooldown_seconds(30, 3), 240);
+    }
+
+    #[test]
+    fn parse_treats_json_as_a_raw_secret_not_stored_state() {
+        // `parse` is reached from untrusted activation input, so a JSON payload must never be
+        // interpreted as our own stored form -- it should fall through to the raw-secret branch
+        // like any other non-`otpauth://` string, not be used to inject `period`/`digits`/lockout
+        // state.
+        let (totp_secret, from_uri) = TotpSecret::parse(r#"{"secret":"JBSWY3DPEHPK3PXP","period":0}"#).unwrap();
+
+        assert!(!from_uri);
+        assert_eq!(totp_secret.period, default_period());
+        assert_eq!(totp_secret.digits, default_digits());
+    }
+
+    #[test]
+    fn parse_uri_rejects_zero_period() {
+        assert!(TotpSecret::parse_uri("otpauth://totp/Example?secret=JBSWY3DPEHPK3PXP&period=0").is_err());
+    }
+
+    #[test]
+    fn parse_uri_rejects_period_above_max() {
+        assert!(TotpSecret::parse_uri("otpauth://totp/Example?secret=JBSWY3DPEHPK3PXP&period=301").is_err());
+    }
+
+    #[test]
+    fn parse_stored_rejects_tampered_period_and_digits() {
+        let tampered = r#"{"secret":"JBSWY3DPEHPK3PXP","digits":9,"period":0}"#;
+        assert!(TotpSecret::parse_stored(tampered).is_err());
+    }
+
+    #[test]
+    fn parse_stored_reads_back_valid_json() {
+        let stored = TotpSecret::parse_uri("otpauth://totp/Example?secret=JBSWY3DPEHPK3PXP&digits=8").unwrap();
+        let serialized = serde_json::to_string(&stored).unwrap();
+
+        let reparsed = TotpSecret::parse_stored(&serialized).unwrap();
+        assert_eq!(reparsed.secret, "JBSWY3DPEHPK3PXP");
+        assert_eq!(reparsed.digits, 8);
+    }
+
+    #[test]
+    fn parse_stored_falls_back_to_legacy_raw_secret() {
+        let legacy = TotpSecret::parse_stored("JBSWY3DPEHPK3PXP").unwrap();
+        assert_eq!(legacy.secret, "JBSWY3DPEHPK3PXP");
+        assert_eq!(legacy.period, default_period());
+    }
+}